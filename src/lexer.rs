@@ -1,20 +1,21 @@
+use std::borrow::Cow;
 use std::fmt::Display;
-use std::str::FromStr;
+use std::str::{Chars, FromStr};
 
 const WHITESPACE: [char; 4] = ['\t', '\r', ' ', '\n'];
 const PUNCTUATION: [char; 8] = ['(', ')', '[', ']', '{', '}', ':', ','];
 const NUMBER_CHAR: [char; 5] = ['-', '+', '.', 'e', 'E'];
 
 #[derive(Clone, Debug, PartialEq)]
-pub(crate) enum Token {
-    String(String),
+pub(crate) enum Token<'a> {
+    String(Cow<'a, str>),
     Number(f64),
     Bool(bool),
     Null,
     Punct(char),
 }
 
-impl Display for Token {
+impl<'a> Display for Token<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Token::String(s) => write!(f, "{}", s),
@@ -26,14 +27,26 @@ impl Display for Token {
     }
 }
 
+/// Byte range of a token within the original input, plus the line/column
+/// of its first character, so callers can render precise diagnostics.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
 pub(crate) struct Lexer {
     line: usize,
     col: usize,
     index: usize,
+    comments: bool,
 }
 
 #[derive(Debug)]
 pub struct Error {
+    message: &'static str,
     text: String,
     line: usize,
     col: usize,
@@ -41,17 +54,51 @@ pub struct Error {
 
 impl std::error::Error for Error {}
 
+/// Renders the ANSI caret-underlined snippet shared by lex and parse errors.
+pub(crate) fn render_snippet(line: usize, col: usize, text: &str) -> String {
+    let margin = (col + 1).to_string().len() + 1;
+    let col = col + 1;
+    format!(
+        "{:>margin$}\n{} |{}\n{:>margin$}\x1b[31;1m{:>col$}\x1b[0m\n",
+        "|", line, text, "|", "^"
+    )
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let margin = (self.col + 1).to_string().len() + 1;
-        let col = self.col + 1;
-        write!(f,
-                "\x1b[31;1merror\x1b[0m: unexpected character\n{:>margin$}\n{} |{}\n{:>margin$}\x1b[31;1m{:>col$}\x1b[0m\n",
-                "|",
-                self.line,
-                self.text,
-                "|",
-                "^")
+        write!(
+            f,
+            "\x1b[31;1merror\x1b[0m: {}\n{}",
+            self.message,
+            render_snippet(self.line, self.col, &self.text)
+        )
+    }
+}
+
+/// Lazily pulls `(Token, Span)` pairs from a [`Lexer`] one at a time, so a
+/// caller can abort on the first error without tokenizing the rest of the
+/// input. Returned by [`Lexer::tokens`].
+pub(crate) struct Tokens<'a> {
+    lexer: Lexer,
+    input: &'a str,
+    errored: bool,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<(Token<'a>, Span), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+        match self.lexer.next_token(self.input) {
+            Ok(Some(tok)) => Some(Ok(tok)),
+            Ok(None) => None,
+            Err(e) => {
+                self.errored = true;
+                Some(Err(e))
+            }
+        }
     }
 }
 
@@ -61,38 +108,154 @@ impl Lexer {
             line: 0,
             col: 0,
             index: 0,
+            comments: false,
         }
     }
 
-    fn lex_string(&mut self, input: &str) -> Option<String> {
-        let mut chars = input[self.index..].chars().peekable();
+    /// Enables `//` and `/* */` comments, consumed as whitespace. Off by
+    /// default to keep strict RFC-8259 behavior.
+    pub(crate) fn with_comments(mut self, enabled: bool) -> Self {
+        self.comments = enabled;
+        self
+    }
+
+    /// Wraps this lexer in an [`Iterator`] that yields one token at a time
+    /// instead of tokenizing `input` up front.
+    pub(crate) fn tokens(self, input: &str) -> Tokens<'_> {
+        Tokens {
+            lexer: self,
+            input,
+            errored: false,
+        }
+    }
+
+    fn error_here(&self, input: &str, message: &'static str) -> Error {
+        Error {
+            message,
+            text: input.lines().nth(self.line).unwrap_or("").to_string(),
+            line: self.line,
+            col: self.col,
+        }
+    }
+
+    /// Reads exactly four hex digits (a `\uXXXX` payload) and returns the
+    /// code unit they encode.
+    fn read_hex4(&mut self, chars: &mut std::iter::Peekable<Chars>, input: &str) -> Result<u32, Error> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let c = chars.next().ok_or_else(|| self.error_here(input, "unexpected character"))?;
+            let digit = c.to_digit(16).ok_or_else(|| self.error_here(input, "unexpected character"))?;
+            self.index += c.len_utf8();
+            self.col += 1;
+            value = value * 16 + digit;
+        }
+        Ok(value)
+    }
 
-        if chars.peek() == Some(&'"') {
-            chars.next();
+    /// Reads a `\uXXXX` escape (already past the `\u`) and decodes it,
+    /// combining it with a following low surrogate when it is a high
+    /// surrogate.
+    fn read_unicode_escape(
+        &mut self,
+        chars: &mut std::iter::Peekable<Chars>,
+        input: &str,
+    ) -> Result<char, Error> {
+        let hi = self.read_hex4(chars, input)?;
+
+        if (0xD800..=0xDBFF).contains(&hi) {
+            if chars.next() != Some('\\') {
+                return Err(self.error_here(input, "unexpected character"));
+            }
+            self.index += 1;
+            self.col += 1;
+            if chars.next() != Some('u') {
+                return Err(self.error_here(input, "unexpected character"));
+            }
             self.index += 1;
             self.col += 1;
-            let mut s = String::new();
 
-            let mut escape = false;
+            let lo = self.read_hex4(chars, input)?;
+            if !(0xDC00..=0xDFFF).contains(&lo) {
+                return Err(self.error_here(input, "unexpected character"));
+            }
 
-            for ch in chars {
-                self.index += 1;
-                self.col += 1;
-                if escape {
-                    s.push(ch);
-                    escape = false;
-                } else if ch == '\\' {
-                    s.push(ch);
-                    escape = true;
-                } else if ch == '"' {
-                    break;
-                } else {
-                    s.push(ch);
+            let code_point = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+            char::from_u32(code_point).ok_or_else(|| self.error_here(input, "unexpected character"))
+        } else if (0xDC00..=0xDFFF).contains(&hi) {
+            // unpaired low surrogate
+            Err(self.error_here(input, "unexpected character"))
+        } else {
+            char::from_u32(hi).ok_or_else(|| self.error_here(input, "unexpected character"))
+        }
+    }
+
+    /// Scans a JSON string literal, decoding escape sequences. Borrows
+    /// directly from `input` when the string has none, and only allocates
+    /// once it has to materialize a decoded escape.
+    fn lex_string<'a>(&mut self, input: &'a str) -> Result<Option<Cow<'a, str>>, Error> {
+        let mut chars = input[self.index..].chars().peekable();
+
+        if chars.peek() != Some(&'"') {
+            return Ok(None);
+        }
+        chars.next();
+        self.index += 1;
+        self.col += 1;
+
+        let mut start = self.index;
+        let mut owned: Option<String> = None;
+
+        loop {
+            let ch = match chars.next() {
+                Some(ch) => ch,
+                None => {
+                    // unterminated string: treat whatever is left as the content
+                    let rest = &input[start..self.index];
+                    return Ok(Some(match owned {
+                        Some(mut s) => {
+                            s.push_str(rest);
+                            Cow::Owned(s)
+                        }
+                        None => Cow::Borrowed(rest),
+                    }));
                 }
+            };
+            self.index += ch.len_utf8();
+            self.col += 1;
+
+            if ch == '"' {
+                let content = &input[start..self.index - 1];
+                return Ok(Some(match owned {
+                    Some(mut s) => {
+                        s.push_str(content);
+                        Cow::Owned(s)
+                    }
+                    None => Cow::Borrowed(content),
+                }));
+            } else if ch == '\\' {
+                let unescaped = &input[start..self.index - 1];
+                let buf = owned.get_or_insert_with(String::new);
+                buf.push_str(unescaped);
+
+                let escape = chars.next().ok_or_else(|| self.error_here(input, "unexpected character"))?;
+                self.index += escape.len_utf8();
+                self.col += 1;
+
+                let decoded = match escape {
+                    '"' => '"',
+                    '\\' => '\\',
+                    '/' => '/',
+                    'b' => '\u{0008}',
+                    'f' => '\u{000C}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => self.read_unicode_escape(&mut chars, input)?,
+                    _ => return Err(self.error_here(input, "unexpected character")),
+                };
+                buf.push(decoded);
+                start = self.index;
             }
-            Some(s)
-        } else {
-            None
         }
     }
 
@@ -148,74 +311,171 @@ impl Lexer {
         }
     }
 
-    fn eat_whitespace(&mut self, input: &str) -> bool {
-        let mut is_whitespace = false;
-        let mut chars = input[self.index..].chars().peekable();
-        while WHITESPACE.contains(&chars.peek().unwrap_or(&'X')) {
-            if chars.peek() == Some(&'\n') {
-                self.line += 1;
-                self.col = 0;
+    /// Consumes plain whitespace, and, when `self.comments` is set, `//`
+    /// line comments and `/* */` block comments too.
+    fn eat_whitespace(&mut self, input: &str) -> Result<bool, Error> {
+        let mut ate_anything = false;
+
+        loop {
+            let mut chars = input[self.index..].chars().peekable();
+            while WHITESPACE.contains(&chars.peek().unwrap_or(&'X')) {
+                if chars.peek() == Some(&'\n') {
+                    self.line += 1;
+                    self.col = 0;
+                } else {
+                    self.col += 1;
+                }
+                self.index += 1;
+                chars.next();
+                ate_anything = true;
+            }
+
+            if !self.comments {
+                break;
+            }
+
+            if input[self.index..].starts_with("//") {
+                self.index += 2;
+                self.col += 2;
+                while let Some(c) = input[self.index..].chars().next() {
+                    if c == '\n' {
+                        break;
+                    }
+                    self.index += c.len_utf8();
+                    self.col += 1;
+                }
+                ate_anything = true;
+            } else if input[self.index..].starts_with("/*") {
+                self.index += 2;
+                self.col += 2;
+                loop {
+                    if input[self.index..].starts_with("*/") {
+                        self.index += 2;
+                        self.col += 2;
+                        break;
+                    }
+                    match input[self.index..].chars().next() {
+                        None => return Err(self.error_here(input, "unterminated block comment")),
+                        Some('\n') => {
+                            self.line += 1;
+                            self.col = 0;
+                            self.index += 1;
+                        }
+                        Some(c) => {
+                            self.col += 1;
+                            self.index += c.len_utf8();
+                        }
+                    }
+                }
+                ate_anything = true;
             } else {
-                self.col += 1;
+                break;
             }
-            self.index += 1;
-            chars.next();
-            is_whitespace = true;
         }
-        is_whitespace
+
+        Ok(ate_anything)
     }
 
-    pub(crate) fn lex(&mut self, input: &str) -> Result<Vec<Token>, Error> {
+    /// Advances past exactly one token (skipping any leading whitespace) and
+    /// returns it, or `None` once `input` is exhausted. [`Lexer::lex`] and
+    /// [`Tokens`] are both built on top of this.
+    pub(crate) fn next_token<'a>(&mut self, input: &'a str) -> Result<Option<(Token<'a>, Span)>, Error> {
         let input_len = input.len();
-        let mut tokens = vec![];
 
         while self.index < input_len {
-            // whitespace
-            if self.eat_whitespace(input) {
+            // whitespace (and comments, when enabled)
+            if self.eat_whitespace(input)? {
                 continue;
             }
 
+            let start = self.index;
+            let (line, col) = (self.line, self.col);
+
             // string
-            if let Some(s) = self.lex_string(input) {
-                tokens.push(Token::String(s));
-                continue;
+            if let Some(s) = self.lex_string(input)? {
+                return Ok(Some((
+                    Token::String(s),
+                    Span {
+                        start,
+                        end: self.index,
+                        line,
+                        col,
+                    },
+                )));
             }
 
             // number
-            if let Some(i) = self.lex_number(input) {
-                tokens.push(Token::Number(i));
-                continue;
+            if let Some(n) = self.lex_number(input) {
+                return Ok(Some((
+                    Token::Number(n),
+                    Span {
+                        start,
+                        end: self.index,
+                        line,
+                        col,
+                    },
+                )));
             }
 
             // boolean
             if let Some(b) = self.lex_boolean(input) {
-                tokens.push(Token::Bool(b));
-                continue;
+                return Ok(Some((
+                    Token::Bool(b),
+                    Span {
+                        start,
+                        end: self.index,
+                        line,
+                        col,
+                    },
+                )));
             }
 
             // null
             if self.lex_null(input) {
-                tokens.push(Token::Null);
-                continue;
+                return Ok(Some((
+                    Token::Null,
+                    Span {
+                        start,
+                        end: self.index,
+                        line,
+                        col,
+                    },
+                )));
             }
 
             // punctuation
             let mut chars = input[self.index..].chars().peekable();
             if PUNCTUATION.contains(&chars.peek().unwrap_or(&' ')) {
-                tokens.push(Token::Punct(chars.next().unwrap()));
+                let c = chars.next().unwrap();
                 self.index += 1;
                 self.col += 1;
-                continue;
+                return Ok(Some((
+                    Token::Punct(c),
+                    Span {
+                        start,
+                        end: self.index,
+                        line,
+                        col,
+                    },
+                )));
             }
 
             // otherwise return error
-            return Err(Error {
-                text: input.lines().nth(self.line).unwrap().to_string(),
-                line: (self.col + 1).to_string().len() + 1,
-                col: self.col,
-            });
+            return Err(self.error_here(input, "unexpected character"));
         }
 
+        Ok(None)
+    }
+
+    /// Batch-tokenizes `input` in one call. Superseded by [`Lexer::next_token`]
+    /// and [`Lexer::tokens`] everywhere but tests, where collecting the whole
+    /// stream up front is the more convenient shape to assert against.
+    #[cfg(test)]
+    pub(crate) fn lex<'a>(&mut self, input: &'a str) -> Result<Vec<(Token<'a>, Span)>, Error> {
+        let mut tokens = vec![];
+        while let Some(tok) = self.next_token(input)? {
+            tokens.push(tok);
+        }
         Ok(tokens)
     }
 }
@@ -224,25 +484,29 @@ impl Lexer {
 mod tests {
     use super::*;
 
+    fn toks<'a>(tokens: &[(Token<'a>, Span)]) -> Vec<Token<'a>> {
+        tokens.iter().map(|(t, _)| t.clone()).collect()
+    }
+
     #[test]
     fn empty_string() {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex("").unwrap();
-        assert_eq!(&tokens[..], []);
+        assert_eq!(&toks(&tokens)[..], []);
     }
 
     #[test]
     fn just_whitespace() {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex("        ").unwrap();
-        assert_eq!(&tokens[..], []);
+        assert_eq!(&toks(&tokens)[..], []);
     }
 
     #[test]
     fn just_null() {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex("null").unwrap();
-        assert_eq!(&tokens[..], [Token::Null]);
+        assert_eq!(&toks(&tokens)[..], [Token::Null]);
     }
 
     #[test]
@@ -250,10 +514,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": 123}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Number(123.0),
                 Token::Punct('}')
@@ -266,10 +530,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": 123.0}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Number(123.0),
                 Token::Punct('}')
@@ -282,10 +546,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": -2}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Number(-2.0),
                 Token::Punct('}')
@@ -298,10 +562,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": -2.0}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Number(-2.0),
                 Token::Punct('}')
@@ -314,10 +578,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": 1.0E+2}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Number(100.0),
                 Token::Punct('}')
@@ -330,10 +594,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": true}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Bool(true),
                 Token::Punct('}')
@@ -346,10 +610,10 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(r#"{"key": null}"#).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
                 Token::Null,
                 Token::Punct('}')
@@ -376,7 +640,7 @@ mod tests {
         let mut lexer = Lexer::new();
         let tokens = lexer.lex(input).unwrap();
         assert_eq!(
-            &tokens[..],
+            &toks(&tokens)[..],
             [
                 Token::Punct('['),
                 Token::Bool(true),
@@ -394,12 +658,163 @@ mod tests {
                 Token::Number(-194037878.6297381),
                 Token::Punct(','),
                 Token::Punct('{'),
-                Token::String("key".to_string()),
+                Token::String(Cow::Borrowed("key")),
                 Token::Punct(':'),
-                Token::String("value".to_string()),
+                Token::String(Cow::Borrowed("value")),
                 Token::Punct('}'),
                 Token::Punct(']'),
             ]
         );
     }
+
+    #[test]
+    fn token_spans() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.lex(r#"{"key": 123}"#).unwrap();
+        assert_eq!(
+            tokens[0].1,
+            Span {
+                start: 0,
+                end: 1,
+                line: 0,
+                col: 0
+            }
+        );
+        assert_eq!(
+            tokens[1].1,
+            Span {
+                start: 1,
+                end: 6,
+                line: 0,
+                col: 1
+            }
+        );
+        assert_eq!(
+            tokens[3].1,
+            Span {
+                start: 8,
+                end: 11,
+                line: 0,
+                col: 8
+            }
+        );
+    }
+
+    #[test]
+    fn next_token_matches_batch_lex() {
+        let input = r#"{"key": [1, 2, true]}"#;
+        let batch = Lexer::new().lex(input).unwrap();
+
+        let mut incremental = vec![];
+        let mut lexer = Lexer::new();
+        while let Some(tok) = lexer.next_token(input).unwrap() {
+            incremental.push(tok);
+        }
+
+        assert_eq!(batch, incremental);
+    }
+
+    #[test]
+    fn tokens_iterator_yields_same_tokens() {
+        let input = r#"[1, "two", null]"#;
+        let batch = Lexer::new().lex(input).unwrap();
+        let via_iter: Result<Vec<_>, _> = Lexer::new().tokens(input).collect();
+        assert_eq!(batch, via_iter.unwrap());
+    }
+
+    #[test]
+    fn tokens_iterator_stops_after_error() {
+        let input = "[1, @, 2]";
+        let mut iter = Lexer::new().tokens(input);
+        assert!(iter.next().unwrap().is_ok()); // '['
+        assert!(iter.next().unwrap().is_ok()); // 1
+        assert!(iter.next().unwrap().is_ok()); // ','
+        assert!(iter.next().unwrap().is_err()); // '@'
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn string_without_escapes_is_borrowed() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.lex(r#""hello""#).unwrap();
+        match &tokens[0].0 {
+            Token::String(s) => assert!(matches!(s, Cow::Borrowed(_))),
+            t => panic!("expected string token, got {:?}", t),
+        }
+    }
+
+    #[test]
+    fn simple_escapes_are_decoded() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.lex(r#""a\nb\tc\\d\"e\/f""#).unwrap();
+        assert_eq!(tokens[0].0, Token::String(Cow::Borrowed("a\nb\tc\\d\"e/f")));
+    }
+
+    #[test]
+    fn unicode_escape_is_decoded() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.lex("\"A\\u00e9\"").unwrap();
+        assert_eq!(tokens[0].0, Token::String(Cow::Borrowed("A\u{e9}")));
+    }
+
+    #[test]
+    fn surrogate_pair_is_combined() {
+        let mut lexer = Lexer::new();
+        let tokens = lexer.lex("\"\\uD83D\\uDE00\"").unwrap();
+        assert_eq!(tokens[0].0, Token::String(Cow::Borrowed("\u{1F600}")));
+    }
+
+    #[test]
+    fn unknown_escape_is_an_error() {
+        let mut lexer = Lexer::new();
+        assert!(lexer.lex(r#""\q""#).is_err());
+    }
+
+    #[test]
+    fn truncated_unicode_escape_is_an_error() {
+        let mut lexer = Lexer::new();
+        assert!(lexer.lex(r#""\u12""#).is_err());
+    }
+
+    #[test]
+    fn unpaired_high_surrogate_is_an_error() {
+        let mut lexer = Lexer::new();
+        assert!(lexer.lex(r#""\uD800""#).is_err());
+    }
+
+    #[test]
+    fn unpaired_low_surrogate_is_an_error() {
+        let mut lexer = Lexer::new();
+        assert!(lexer.lex(r#""\uDC00""#).is_err());
+    }
+
+    #[test]
+    fn line_comment_is_ignored_when_enabled() {
+        let mut lexer = Lexer::new().with_comments(true);
+        let tokens = lexer.lex("1 // a trailing comment\n, 2").unwrap();
+        assert_eq!(
+            &toks(&tokens)[..],
+            [Token::Number(1.0), Token::Punct(','), Token::Number(2.0)]
+        );
+    }
+
+    #[test]
+    fn block_comment_is_ignored_when_enabled() {
+        let mut lexer = Lexer::new().with_comments(true);
+        let tokens = lexer.lex("1 /* a\nmulti-line\ncomment */ 2").unwrap();
+        assert_eq!(&toks(&tokens)[..], [Token::Number(1.0), Token::Number(2.0)]);
+        assert_eq!(tokens[1].1.line, 2);
+    }
+
+    #[test]
+    fn unterminated_block_comment_is_an_error() {
+        let mut lexer = Lexer::new().with_comments(true);
+        assert!(lexer.lex("1 /* never closed").is_err());
+    }
+
+    #[test]
+    fn comments_are_unexpected_characters_when_disabled() {
+        let mut lexer = Lexer::new();
+        assert!(lexer.lex("1 // not a comment here").is_err());
+    }
 }
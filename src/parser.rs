@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
 use crate::lexer;
+use crate::lexer::Span;
 use std::collections::HashMap;
-use std::iter::{Iterator, Peekable};
+use std::iter::Peekable;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value {
@@ -14,12 +15,18 @@ pub enum Value {
     String(String),
 }
 
-pub struct Parser {}
+pub struct Parser {
+    comments: bool,
+}
 
 #[derive(Debug)]
 pub enum Error {
     LexError(lexer::Error),
-    ParseError(String),
+    ParseError {
+        message: String,
+        line_text: String,
+        span: Span,
+    },
 }
 
 impl From<lexer::Error> for Error {
@@ -33,114 +40,222 @@ impl std::error::Error for Error {}
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Error::LexError(le) => write!(f,"{}",le),
-            Error::ParseError(s) => write!(f, "\x1b[31;1merror\x1b[0m: {}\x1b[0m\n", s),
-        } 
+            Error::LexError(le) => write!(f, "{}", le),
+            Error::ParseError {
+                message,
+                line_text,
+                span,
+            } => write!(
+                f,
+                "\x1b[31;1merror\x1b[0m: {}\n{}",
+                message,
+                lexer::render_snippet(span.line, span.col, line_text)
+            ),
+        }
     }
 }
 
+type TokenStream<'a> = Peekable<lexer::Tokens<'a>>;
+
+/// Pulls the next token, surfacing a lex error that occurred while
+/// advancing the stream as a `parser::Error`.
+fn next_tok<'a>(tokens: &mut TokenStream<'a>) -> Result<Option<(lexer::Token<'a>, Span)>, Error> {
+    tokens.next().transpose().map_err(Error::from)
+}
+
+/// Peeks at the next token without consuming it, surfacing a lex error the
+/// same way [`next_tok`] does instead of leaving it for the next peek/next.
+fn peek_tok<'a, 'b>(
+    tokens: &'b mut TokenStream<'a>,
+) -> Result<Option<&'b (lexer::Token<'a>, Span)>, Error> {
+    if matches!(tokens.peek(), Some(Err(_))) {
+        let err = tokens.next().unwrap().unwrap_err();
+        return Err(err.into());
+    }
+    Ok(tokens.peek().map(|res| res.as_ref().unwrap()))
+}
+
 impl Parser {
     pub fn new() -> Self {
-        Parser {}
+        Parser { comments: false }
+    }
+
+    /// Enables a JSONC/JSON5-ish dialect: `//` and `/* */` comments, and a
+    /// trailing comma before a list's `]` or an object's `}`. Off by
+    /// default, so strict RFC-8259 parsing is unaffected.
+    pub fn with_comments(mut self, enabled: bool) -> Self {
+        self.comments = enabled;
+        self
     }
 
     pub fn parse(&self, input: &str) -> Result<Option<Value>, Error> {
-        let tokens = lexer::Lexer::new().lex(input)?;
+        let mut tokens = lexer::Lexer::new()
+            .with_comments(self.comments)
+            .tokens(input)
+            .peekable();
 
-        if tokens.is_empty() {
+        if peek_tok(&mut tokens)?.is_none() {
             return Ok(None);
         }
 
-        let value = self.parse_value(&mut tokens.iter().peekable())?;
+        let value = self.parse_value(&mut tokens, input)?;
         Ok(Some(value))
     }
 
-    fn parse_value<'a, I>(&self, tokens: &mut Peekable<I>) -> Result<Value, Error>
-    where
-        I: Iterator<Item = &'a lexer::Token>,
-    {
-        match tokens.next().as_ref() {
-            Some(lexer::Token::Null) => Ok(Value::Null),
-            Some(lexer::Token::Bool(b)) => Ok(Value::Boolean(*b)),
-            Some(lexer::Token::Number(n)) => Ok(Value::Number(*n)),
-            Some(lexer::Token::String(s)) => Ok(Value::String(s.clone())),
-            Some(lexer::Token::Punct('[')) => {
+    fn error_at(&self, input: &str, span: Span, message: String) -> Error {
+        Error::ParseError {
+            message,
+            line_text: input.lines().nth(span.line).unwrap_or("").to_string(),
+            span,
+        }
+    }
+
+    fn parse_value(&self, tokens: &mut TokenStream, input: &str) -> Result<Value, Error> {
+        match next_tok(tokens)? {
+            Some((lexer::Token::Null, _)) => Ok(Value::Null),
+            Some((lexer::Token::Bool(b), _)) => Ok(Value::Boolean(b)),
+            Some((lexer::Token::Number(n), _)) => Ok(Value::Number(n)),
+            Some((lexer::Token::String(s), _)) => Ok(Value::String(s.into_owned())),
+            Some((lexer::Token::Punct('['), _)) => {
                 let mut list: Vec<Value> = vec![];
                 loop {
-                    if tokens.peek() == Some(&&lexer::Token::Punct(']')) {
+                    if matches!(peek_tok(tokens)?, Some((lexer::Token::Punct(']'), _))) {
                         tokens.next();
                         break;
                     }
 
-                    let value = self.parse_value(tokens)?;
+                    let value = self.parse_value(tokens, input)?;
                     list.push(value);
 
-                    match tokens.peek() {
-                        Some(&lexer::Token::Punct(',')) => {
+                    match peek_tok(tokens)?.map(|(t, s)| (t, *s)) {
+                        Some((lexer::Token::Punct(','), comma_span)) => {
                             tokens.next();
+                            if matches!(peek_tok(tokens)?, Some((lexer::Token::Punct(']'), _))) {
+                                if !self.comments {
+                                    return Err(self.error_at(
+                                        input,
+                                        comma_span,
+                                        "trailing comma is not allowed".to_string(),
+                                    ));
+                                }
+                                tokens.next();
+                                break;
+                            }
                             continue;
                         }
-                        Some(&lexer::Token::Punct(']')) => {
+                        Some((lexer::Token::Punct(']'), _)) => {
                             tokens.next();
                             break;
                         }
                         Some(_) => (),
                         None => {
-                            return Err(Error::ParseError(
+                            return Err(self.error_at(
+                                input,
+                                eof_span(input),
                                 "unexpected end of input while parsing list".to_string(),
                             ))
                         }
                     }
                 }
-                return Ok(Value::List(list));
+                Ok(Value::List(list))
             }
-            Some(&lexer::Token::Punct('{')) => {
+            Some((lexer::Token::Punct('{'), _)) => {
                 let mut hm = HashMap::<String, Value>::new();
                 loop {
-                    if tokens.peek() == Some(&&lexer::Token::Punct('}')) {
+                    if matches!(peek_tok(tokens)?, Some((lexer::Token::Punct('}'), _))) {
                         tokens.next();
                         break;
                     }
 
-                    let key = match self.parse_value(tokens)? {
+                    let key_span = peek_tok(tokens)?
+                        .map(|(_, s)| *s)
+                        .unwrap_or_else(|| eof_span(input));
+                    let key = match self.parse_value(tokens, input)? {
                         Value::String(s) => s,
-                        _ => return Err(Error::ParseError("invalid key value".to_string())),
+                        _ => {
+                            return Err(self.error_at(
+                                input,
+                                key_span,
+                                "invalid key value".to_string(),
+                            ))
+                        }
                     };
 
-                    if tokens.peek() == Some(&&lexer::Token::Punct(':')) {
+                    if matches!(peek_tok(tokens)?, Some((lexer::Token::Punct(':'), _))) {
                         tokens.next();
                     } else {
-                        return Err(Error::ParseError(format!("expected ':', got '{:?}'", tokens.next())));
+                        let (found, span) = match next_tok(tokens)? {
+                            Some((t, s)) => (format!("{:?}", t), s),
+                            None => ("end of input".to_string(), eof_span(input)),
+                        };
+                        return Err(self.error_at(
+                            input,
+                            span,
+                            format!("expected ':', got '{}'", found),
+                        ));
                     }
 
-                    let value = self.parse_value(tokens)?;
+                    let value = self.parse_value(tokens, input)?;
                     hm.insert(key, value);
 
-                    match tokens.peek() {
-                        Some(&lexer::Token::Punct(',')) => {
+                    match peek_tok(tokens)?.map(|(t, s)| (t, *s)) {
+                        Some((lexer::Token::Punct(','), comma_span)) => {
                             tokens.next();
+                            if matches!(peek_tok(tokens)?, Some((lexer::Token::Punct('}'), _))) {
+                                if !self.comments {
+                                    return Err(self.error_at(
+                                        input,
+                                        comma_span,
+                                        "trailing comma is not allowed".to_string(),
+                                    ));
+                                }
+                                tokens.next();
+                                break;
+                            }
                             continue;
                         }
-                        Some(&lexer::Token::Punct('}')) => {
+                        Some((lexer::Token::Punct('}'), _)) => {
                             tokens.next();
                             break;
                         }
                         Some(_) => (),
                         None => {
-                            return Err(Error::ParseError(
+                            return Err(self.error_at(
+                                input,
+                                eof_span(input),
                                 "unexpected end of input while parsing list".to_string(),
                             ))
                         }
                     }
                 }
 
-                return Ok(Value::Object(hm));
+                Ok(Value::Object(hm))
+            }
+            Some((t, span)) => {
+                Err(self.error_at(input, span, format!("unexpected token '{:?}'", t)))
             }
-            t => Err(Error::ParseError(format!("unexpected token '{:?}'", t))),
+            None => Err(self.error_at(
+                input,
+                eof_span(input),
+                "unexpected end of input".to_string(),
+            )),
         }
     }
 }
 
+/// Points diagnostics at the end of the input when the token stream is
+/// exhausted, e.g. a list or object that never sees its closing bracket.
+fn eof_span(input: &str) -> Span {
+    let line = input.lines().count().saturating_sub(1);
+    let col = input.lines().last().map(|l| l.chars().count()).unwrap_or(0);
+    Span {
+        start: input.len(),
+        end: input.len(),
+        line,
+        col,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +352,66 @@ mod tests {
         assert_eq!(value, Value::Object(hm));
     }
 
+    #[test]
+    fn parse_error_reports_span() {
+        let input = "{\"key\": }";
+        let err = Parser::new().parse(&input).unwrap_err();
+        match err {
+            Error::ParseError { span, .. } => assert_eq!(span.line, 0),
+            _ => panic!("expected ParseError"),
+        }
+    }
+
+    #[test]
+    fn aborts_without_lexing_trailing_garbage() {
+        // An error in the very first key must surface without needing the
+        // lexer to ever look at (or choke on) the malformed tail.
+        let input = format!("{{ @ {}", "x".repeat(1_000_000));
+        let err = Parser::new().parse(&input).unwrap_err();
+        assert!(matches!(err, Error::LexError(_)));
+    }
+
+    #[test]
+    fn trailing_comma_is_rejected_by_default() {
+        let input = "[1, 2,]";
+        let err = Parser::new().parse(&input).unwrap_err();
+        match err {
+            Error::ParseError { message, .. } => {
+                assert_eq!(message, "trailing comma is not allowed")
+            }
+            _ => panic!("expected ParseError"),
+        }
+    }
+
+    #[test]
+    fn trailing_comma_is_allowed_with_comments_enabled() {
+        let input = r#"{"key1": 1, "key2": 2,}"#;
+        let mut hm = HashMap::new();
+        hm.insert("key1".to_string(), Value::Number(1.0));
+        hm.insert("key2".to_string(), Value::Number(2.0));
+        let value = Parser::new()
+            .with_comments(true)
+            .parse(&input)
+            .unwrap()
+            .unwrap();
+        assert_eq!(value, Value::Object(hm));
+    }
+
+    #[test]
+    fn comments_are_allowed_with_comments_enabled() {
+        let input = "[\n  1, // first\n  /* second */ 2\n]";
+        let value = Parser::new()
+            .with_comments(true)
+            .parse(&input)
+            .unwrap()
+            .unwrap();
+        assert_eq!(value, Value::List(vec![Value::Number(1.0), Value::Number(2.0)]));
+    }
+
+    #[test]
+    fn comments_are_rejected_by_default() {
+        let input = "[1, // not a comment\n2]";
+        let err = Parser::new().parse(&input).unwrap_err();
+        assert!(matches!(err, Error::LexError(_)));
+    }
 }